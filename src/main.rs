@@ -1,12 +1,14 @@
 use iced::{ executor, Subscription };
 use iced::widget::{
     button,
+    checkbox,
     column,
     container,
     horizontal_space,
     row,
     text,
     text_editor,
+    text_input,
     tooltip,
     pick_list,
 };
@@ -15,7 +17,9 @@ use iced::{ Font, Command, Application, Element, Length, Settings, Theme };
 use iced::theme;
 use iced::highlighter::{ self, Highlighter };
 
+use std::collections::HashMap;
 use std::io;
+use std::ops::Range;
 use std::path::{ Path, PathBuf };
 use std::sync::Arc;
 
@@ -28,58 +32,654 @@ fn main() -> iced::Result {
     })
 }
 
+// Uma EditRecord descreve uma única mudança contígua no texto, em vez de uma
+// cópia completa do buffer; undo/redo aplicam o registro (ou o seu inverso)
+// sobre o texto atual.
+#[derive(Debug, Clone)]
+struct EditRecord {
+    offset: usize,
+    removed: String,
+    inserted: String,
+}
+
+// Computa a menor EditRecord que transforma `old` em `new`, a partir do
+// maior prefixo e sufixo comuns entre os dois.
+fn diff_edit(old: &str, new: &str) -> Option<EditRecord> {
+    if old == new {
+        return None;
+    }
+
+    let old_chars: Vec<char> = old.chars().collect();
+    let new_chars: Vec<char> = new.chars().collect();
+
+    let max_prefix = old_chars.len().min(new_chars.len());
+    let mut prefix = 0;
+    while prefix < max_prefix && old_chars[prefix] == new_chars[prefix] {
+        prefix += 1;
+    }
+
+    let max_suffix = (old_chars.len() - prefix).min(new_chars.len() - prefix);
+    let mut suffix = 0;
+    while
+        suffix < max_suffix &&
+        old_chars[old_chars.len() - 1 - suffix] == new_chars[new_chars.len() - 1 - suffix]
+    {
+        suffix += 1;
+    }
+
+    let removed: String = old_chars[prefix..old_chars.len() - suffix].iter().collect();
+    let inserted: String = new_chars[prefix..new_chars.len() - suffix].iter().collect();
+
+    Some(EditRecord { offset: prefix, removed, inserted })
+}
+
+// Devolve `text` com os caracteres de `replaced`, a partir do offset (em
+// caracteres) `offset`, substituídos por `with`.
+fn splice(text: &str, offset: usize, replaced: &str, with: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let replaced_len = replaced.chars().count();
+
+    let mut result = String::with_capacity(text.len() + with.len());
+    result.extend(&chars[..offset]);
+    result.push_str(with);
+    result.extend(&chars[offset + replaced_len..]);
+    result
+}
+
 struct EditHistory {
-    history: Vec<String>,
-    current_index: usize,
+    undo: Vec<EditRecord>,
+    redo: Vec<EditRecord>,
+    mark: usize,
 }
 
 impl EditHistory {
-    fn new(initial_text: String) -> Self {
+    fn new() -> Self {
+        Self { undo: Vec::new(), redo: Vec::new(), mark: 0 }
+    }
+
+    // Regista a transição de `old` para `new` como um novo passo de undo,
+    // descartando qualquer redo pendente.
+    fn push_edit(&mut self, old: &str, new: &str) {
+        if let Some(record) = diff_edit(old, new) {
+            // O estado salvo estava no redo stack, que vai ser descartado:
+            // deixa de ser alcançável.
+            if self.mark > self.undo.len() {
+                self.mark = usize::MAX;
+            }
+            self.undo.push(record);
+            self.redo.clear();
+        }
+    }
+
+    fn undo(&mut self, current: &str) -> Option<String> {
+        let record = self.undo.pop()?;
+        let text = splice(current, record.offset, &record.inserted, &record.removed);
+        self.redo.push(record);
+        Some(text)
+    }
+
+    fn redo(&mut self, current: &str) -> Option<String> {
+        let record = self.redo.pop()?;
+        let text = splice(current, record.offset, &record.removed, &record.inserted);
+        self.undo.push(record);
+        Some(text)
+    }
+
+    // Marca o estado atual do undo stack como o último ponto salvo.
+    fn mark_saved(&mut self) {
+        self.mark = self.undo.len();
+    }
+
+    // Limpo quando o undo stack está exatamente como estava ao salvar.
+    fn is_clean(&self) -> bool {
+        self.undo.len() == self.mark
+    }
+}
+
+// Pares de caracteres que se fecham automaticamente quando `auto_pair`
+// está ativo. Aspas aparecem com o mesmo caractere dos dois lados.
+const PAIRS: &[(char, char)] = &[
+    ('(', ')'),
+    ('{', '}'),
+    ('[', ']'),
+    ('"', '"'),
+    ('\'', '\''),
+    ('`', '`'),
+];
+
+fn matching_close(open: char) -> Option<char> {
+    PAIRS.iter().find(|(o, _)| *o == open).map(|(_, close)| *close)
+}
+
+fn is_closer(c: char) -> bool {
+    PAIRS.iter().any(|(_, close)| *close == c)
+}
+
+fn is_opener(c: char) -> bool {
+    PAIRS.iter().any(|(open, _)| *open == c)
+}
+
+fn is_quote(c: char) -> bool {
+    c == '"' || c == '\'' || c == '`'
+}
+
+fn char_before_cursor(content: &text_editor::Content) -> Option<char> {
+    let (line, column) = content.cursor_position();
+    column.checked_sub(1).and_then(|index| content.line(line)?.chars().nth(index))
+}
+
+fn char_after_cursor(content: &text_editor::Content) -> Option<char> {
+    let (line, column) = content.cursor_position();
+    content.line(line)?.chars().nth(column)
+}
+
+// Estado do painel de busca/substituição: a consulta atual, as ocorrências
+// (como intervalos de bytes sobre o texto do buffer ativo) e qual delas está
+// selecionada.
+struct SearchState {
+    query: String,
+    replacement: String,
+    matches: Vec<Range<usize>>,
+    current: usize,
+    case_insensitive: bool,
+    whole_word: bool,
+}
+
+impl SearchState {
+    fn new() -> Self {
         Self {
-            history: vec![initial_text],
-            current_index: 0,
+            query: String::new(),
+            replacement: String::new(),
+            matches: Vec::new(),
+            current: 0,
+            case_insensitive: true,
+            whole_word: false,
         }
     }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Gera a versão em minúsculas de `text` junto de um mapeamento do byte
+// de cada caractere minúsculo para o byte correspondente no texto
+// original. to_lowercase() pode mudar o tamanho em bytes de alguns
+// caracteres (p.ex. 'İ' -> "i̇"), então não dá para reaproveitar os
+// offsets da versão em minúsculas diretamente sobre o texto original.
+fn lowercase_with_offsets(text: &str) -> (String, Vec<usize>) {
+    let mut lowered = String::with_capacity(text.len());
+    let mut offsets = Vec::with_capacity(text.len());
 
-    fn add_edit(&mut self, text: String) {
-        // Remove qualquer edição futura se houver
-        if self.current_index < self.history.len() - 1 {
-            self.history.truncate(self.current_index + 1);
+    for (byte_index, ch) in text.char_indices() {
+        for lower_ch in ch.to_lowercase() {
+            for _ in 0..lower_ch.len_utf8() {
+                offsets.push(byte_index);
+            }
+            lowered.push(lower_ch);
         }
-        self.history.push(text);
-        self.current_index += 1;
     }
+    offsets.push(text.len());
 
-    fn undo(&mut self) -> Option<String> {
-        if self.current_index > 0 {
-            self.current_index -= 1;
-            Some(self.history[self.current_index].clone())
-        } else {
-            None
+    (lowered, offsets)
+}
+
+// Encontra todas as ocorrências de `query` em `haystack`, opcionalmente
+// ignorando maiúsculas/minúsculas e exigindo fronteiras de palavra. Os
+// ranges retornados são sempre offsets de bytes válidos em `haystack`
+// (o texto original), mesmo quando a comparação é feita sobre uma
+// versão em minúsculas internamente.
+fn find_matches(
+    haystack: &str,
+    query: &str,
+    case_insensitive: bool,
+    whole_word: bool
+) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let (search_haystack, offsets, query) = if case_insensitive {
+        let (lowered, offsets) = lowercase_with_offsets(haystack);
+        (lowered, Some(offsets), query.to_lowercase())
+    } else {
+        (haystack.to_string(), None, query.to_string())
+    };
+
+    let mut matches = Vec::new();
+    let mut search_start = 0;
+
+    while let Some(found) = search_haystack[search_start..].find(&query) {
+        let lowered_start = search_start + found;
+        let lowered_end = lowered_start + query.len();
+
+        let (start, end) = match &offsets {
+            Some(offsets) => (offsets[lowered_start], offsets[lowered_end]),
+            None => (lowered_start, lowered_end),
+        };
+
+        let starts_word = start == 0 || !haystack[..start].ends_with(is_word_char);
+        let ends_word = end == haystack.len() || !haystack[end..].starts_with(is_word_char);
+
+        if !whole_word || (starts_word && ends_word) {
+            matches.push(start..end);
         }
+
+        search_start = lowered_start + query.len().max(1);
     }
 
-    fn is_clean(&self) -> bool {
-        self.current_index == 0
+    matches
+}
+
+fn replace_byte_range(text: &str, range: &Range<usize>, with: &str) -> String {
+    let mut result = String::with_capacity(text.len() + with.len());
+    result.push_str(&text[..range.start]);
+    result.push_str(with);
+    result.push_str(&text[range.end..]);
+    result
+}
+
+// Move o cursor para o início do documento e avança caractere a caractere
+// até selecionar exatamente `range`; ingênuo, mas o Content não expõe uma
+// forma direta de posicionar o cursor por offset.
+fn select_match(content: &mut text_editor::Content, range: &Range<usize>) {
+    use text_editor::{ Action, Motion };
+
+    content.edit(Action::Move(Motion::DocumentStart));
+
+    let text = content.text();
+    let start_chars = text[..range.start].chars().count();
+    let match_chars = text[range.start..range.end].chars().count();
+
+    for _ in 0..start_chars {
+        content.edit(Action::Move(Motion::Right));
+    }
+
+    for _ in 0..match_chars {
+        content.edit(Action::Select(Motion::Right));
     }
 }
 
-struct Editor {
+// Ações que podem ser associadas a um atalho no keys.toml do utilizador.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum KeyAction {
+    Save,
+    Undo,
+    Redo,
+    New,
+    Open,
+    ToggleExplorer,
+    Search,
+    Cut,
+    Copy,
+    Paste,
+}
+
+impl KeyAction {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "save" => Some(Self::Save),
+            "undo" => Some(Self::Undo),
+            "redo" => Some(Self::Redo),
+            "new" => Some(Self::New),
+            "open" => Some(Self::Open),
+            "toggle_explorer" => Some(Self::ToggleExplorer),
+            "search" => Some(Self::Search),
+            "cut" => Some(Self::Cut),
+            "copy" => Some(Self::Copy),
+            "paste" => Some(Self::Paste),
+            _ => None,
+        }
+    }
+
+    fn to_message(self) -> Message {
+        match self {
+            Self::Save => Message::Save,
+            Self::Undo => Message::Undo,
+            Self::Redo => Message::Redo,
+            Self::New => Message::New,
+            Self::Open => Message::Open,
+            Self::ToggleExplorer => Message::ToggleExplorer,
+            Self::Search => Message::ToggleSearch,
+            Self::Cut => Message::Cut,
+            Self::Copy => Message::Copy,
+            Self::Paste => Message::Paste,
+        }
+    }
+}
+
+// Um Keymap mapeia um chord normalizado (ex: "cmd+shift+z") para a ação que
+// deve disparar.
+#[derive(Debug, Clone)]
+struct Keymap {
+    bindings: HashMap<String, KeyAction>,
+}
+
+impl Keymap {
+    fn lookup(&self, chord: &str) -> Option<KeyAction> {
+        self.bindings.get(chord).copied()
+    }
+}
+
+fn default_keymap() -> Keymap {
+    let bindings = [
+        ("cmd+s", KeyAction::Save),
+        ("cmd+z", KeyAction::Undo),
+        ("cmd+shift+z", KeyAction::Redo),
+        ("cmd+y", KeyAction::Redo),
+        ("cmd+n", KeyAction::New),
+        ("cmd+o", KeyAction::Open),
+        ("cmd+e", KeyAction::ToggleExplorer),
+        ("cmd+f", KeyAction::Search),
+        ("cmd+c", KeyAction::Copy),
+        ("cmd+x", KeyAction::Cut),
+        ("cmd+v", KeyAction::Paste),
+    ]
+        .into_iter()
+        .map(|(chord, action)| (normalize_chord(chord), action))
+        .collect();
+
+    Keymap { bindings }
+}
+
+// Normaliza um chord do keys.toml (ordem livre, qualquer caixa) para o
+// formato usado internamente para comparação.
+fn normalize_chord(chord: &str) -> String {
+    let mut parts: Vec<String> = chord
+        .split('+')
+        .map(|part| part.trim().to_lowercase())
+        .collect();
+    parts.sort();
+    parts.join("+")
+}
+
+fn parse_keymap(source: &str) -> HashMap<String, KeyAction> {
+    let raw: HashMap<String, String> = toml::from_str(source).unwrap_or_default();
+
+    raw.into_iter()
+        .filter_map(|(chord, action)| {
+            KeyAction::from_name(&action).map(|action| (normalize_chord(&chord), action))
+        })
+        .collect()
+}
+
+fn key_label(key_code: keyboard::KeyCode) -> Option<&'static str> {
+    use keyboard::KeyCode::*;
+
+    Some(match key_code {
+        A => "a",
+        C => "c",
+        E => "e",
+        F => "f",
+        N => "n",
+        O => "o",
+        S => "s",
+        V => "v",
+        X => "x",
+        Y => "y",
+        Z => "z",
+        _ => {
+            return None;
+        }
+    })
+}
+
+fn chord_for(key_code: keyboard::KeyCode, modifiers: keyboard::Modifiers) -> Option<String> {
+    let key = key_label(key_code)?;
+
+    let mut parts = vec![key];
+    if modifiers.command() {
+        parts.push("cmd");
+    }
+    if modifiers.shift() {
+        parts.push("shift");
+    }
+    if modifiers.alt() {
+        parts.push("alt");
+    }
+    parts.sort();
+
+    Some(parts.join("+"))
+}
+
+// Abstrai o clipboard do sistema para que o editor continue testável sem um
+// display/servidor de clipboard disponível (ex: execução headless).
+trait ClipboardProvider {
+    fn get_contents(&mut self) -> Result<String, Error>;
+    fn set_contents(&mut self, contents: String) -> Result<(), Error>;
+}
+
+struct SystemClipboard {
+    inner: arboard::Clipboard,
+}
+
+impl SystemClipboard {
+    fn new() -> Result<Self, Error> {
+        arboard::Clipboard::new().map(|inner| Self { inner }).map_err(|_| Error::ClipboardUnavailable)
+    }
+}
+
+impl ClipboardProvider for SystemClipboard {
+    fn get_contents(&mut self) -> Result<String, Error> {
+        self.inner.get_text().map_err(|_| Error::ClipboardUnavailable)
+    }
+
+    fn set_contents(&mut self, contents: String) -> Result<(), Error> {
+        self.inner.set_text(contents).map_err(|_| Error::ClipboardUnavailable)
+    }
+}
+
+// Usado quando não há um clipboard de sistema disponível (headless/testes).
+#[derive(Default)]
+struct InMemoryClipboard {
+    contents: String,
+}
+
+impl ClipboardProvider for InMemoryClipboard {
+    fn get_contents(&mut self) -> Result<String, Error> {
+        Ok(self.contents.clone())
+    }
+
+    fn set_contents(&mut self, contents: String) -> Result<(), Error> {
+        self.contents = contents;
+        Ok(())
+    }
+}
+
+fn default_clipboard() -> Box<dyn ClipboardProvider> {
+    match SystemClipboard::new() {
+        Ok(clipboard) => Box::new(clipboard),
+        Err(_) => Box::new(InMemoryClipboard::default()),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DirEntry {
+    path: PathBuf,
+    name: String,
+    is_dir: bool,
+}
+
+// Estado do sidebar: a raiz que está a ser navegada e, para cada diretório
+// expandido, os seus filhos (lidos de forma preguiçosa ao expandir). Recolher
+// um diretório descarta os seus filhos; expandi-lo de novo volta a lê-los.
+struct TreeState {
+    root: PathBuf,
+    expanded: HashMap<PathBuf, Vec<DirEntry>>,
+}
+
+impl TreeState {
+    fn new(root: PathBuf) -> Self {
+        Self { root, expanded: HashMap::new() }
+    }
+}
+
+// Um Buffer é um único ficheiro aberto: o seu caminho, conteúdo, estado de
+// sujidade e o seu próprio histórico de undo/redo.
+struct Buffer {
     path: Option<PathBuf>,
     content: text_editor::Content,
     error: Option<Error>,
-    theme: highlighter::Theme,
     is_dirty: bool,
-    block_edit: bool,
     edit_history: EditHistory,
 }
 
+impl Buffer {
+    fn new() -> Self {
+        Self {
+            path: None,
+            content: text_editor::Content::new(),
+            error: None,
+            is_dirty: true,
+            edit_history: EditHistory::new(),
+        }
+    }
+
+    fn title(&self) -> String {
+        self.path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| String::from("New file"))
+    }
+
+    // Aplica uma Action ao conteúdo, inserindo/removendo o par correspondente
+    // quando `auto_pair` está ativo.
+    fn apply_edit(&mut self, auto_pair: bool, action: text_editor::Action) {
+        use text_editor::{ Action as A, Edit, Motion };
+
+        if !auto_pair {
+            self.content.edit(action);
+            return;
+        }
+
+        let edit = match &action {
+            A::Edit(edit) => edit.clone(),
+            _ => {
+                self.content.edit(action);
+                return;
+            }
+        };
+
+        match edit {
+            Edit::Insert(c) if is_closer(c) && char_after_cursor(&self.content) == Some(c) => {
+                self.content.edit(A::Move(Motion::Right));
+            }
+            Edit::Insert(c)
+                if
+                    is_quote(c) &&
+                    !char_before_cursor(&self.content)
+                        .map(|before| before.is_whitespace() || is_opener(before))
+                        .unwrap_or(true)
+            => {
+                self.content.edit(action);
+            }
+            Edit::Insert(c) => {
+                self.content.edit(action);
+
+                if let Some(closer) = matching_close(c) {
+                    self.content.edit(A::Edit(Edit::Insert(closer)));
+                    self.content.edit(A::Move(Motion::Left));
+                }
+            }
+            Edit::Backspace => {
+                let before = char_before_cursor(&self.content);
+                let after = char_after_cursor(&self.content);
+
+                self.content.edit(action);
+
+                if let (Some(open), Some(close)) = (before, after) {
+                    if matching_close(open) == Some(close) {
+                        self.content.edit(A::Edit(Edit::Delete));
+                    }
+                }
+            }
+            _ => self.content.edit(action),
+        }
+    }
+}
+
+struct Editor {
+    buffers: Vec<Buffer>,
+    active: usize,
+    theme: highlighter::Theme,
+    block_edit: bool,
+    auto_pair: bool,
+    explorer: Option<TreeState>,
+    pending_close: Option<usize>,
+    search: Option<SearchState>,
+    keymap: Keymap,
+    clipboard: Box<dyn ClipboardProvider>,
+}
+
+impl Editor {
+    fn active_buffer(&self) -> Option<&Buffer> {
+        self.buffers.get(self.active)
+    }
+
+    fn active_buffer_mut(&mut self) -> Option<&mut Buffer> {
+        self.buffers.get_mut(self.active)
+    }
+
+    fn push_buffer(&mut self, buffer: Buffer) {
+        self.buffers.push(buffer);
+        self.active = self.buffers.len() - 1;
+    }
+
+    fn close_tab(&mut self, index: usize) {
+        if index >= self.buffers.len() {
+            return;
+        }
+
+        self.buffers.remove(index);
+
+        if self.active >= self.buffers.len() {
+            self.active = self.buffers.len().saturating_sub(1);
+        } else if index < self.active {
+            self.active -= 1;
+        }
+
+        self.pending_close = match self.pending_close {
+            Some(pending) if pending == index => None,
+            Some(pending) if pending > index => Some(pending - 1),
+            pending => pending,
+        };
+    }
+
+    // Recalcula as ocorrências da busca sobre o buffer ativo e realça a
+    // ocorrência atual.
+    fn refresh_search(&mut self) {
+        let text = self.active_buffer().map(|buffer| buffer.content.text()).unwrap_or_default();
+
+        if let Some(search) = self.search.as_mut() {
+            search.matches = find_matches(&text, &search.query, search.case_insensitive, search.whole_word);
+
+            if search.current >= search.matches.len() {
+                search.current = 0;
+            }
+        }
+
+        self.select_current_match();
+    }
+
+    fn select_current_match(&mut self) {
+        let range = self.search.as_ref().and_then(|search| search.matches.get(search.current).cloned());
+
+        if let Some(range) = range {
+            if let Some(buffer) = self.active_buffer_mut() {
+                select_match(&mut buffer.content, &range);
+            }
+        }
+    }
+}
+
 // Messages should generally to be clone because they represent pure events
 #[derive(Debug, Clone)]
 enum Message {
     Edit(text_editor::Action),
     New,
     Open,
+    OpenFile(PathBuf),
     FileOpened(Result<(PathBuf, Arc<String>), Error>),
     Save,
     FileSaved(Result<PathBuf, Error>),
@@ -87,6 +687,28 @@ enum Message {
     BlockEdit,
     UnblockEdit,
     Undo,
+    Redo,
+    ToggleAutoPair,
+    ToggleExplorer,
+    OpenDir(PathBuf),
+    DirLoaded(Result<(PathBuf, Vec<DirEntry>), Error>),
+    SelectTab(usize),
+    CloseTab(usize),
+    ConfirmClose,
+    CancelClose,
+    ToggleSearch,
+    SearchQueryChanged(String),
+    ReplacementChanged(String),
+    ToggleSearchCase,
+    ToggleWholeWord,
+    FindNext,
+    FindPrev,
+    ReplaceOne,
+    ReplaceAll,
+    KeymapLoaded(Keymap),
+    Cut,
+    Copy,
+    Paste,
 }
 
 impl Application for Editor {
@@ -102,15 +724,23 @@ impl Application for Editor {
     fn new(_flags: Self::Flags) -> (Self, Command<Message>) {
         (
             Self {
-                path: None,
-                content: text_editor::Content::new(),
-                error: None,
+                buffers: Vec::new(),
+                active: 0,
                 theme: highlighter::Theme::Base16Mocha,
-                is_dirty: true,
                 block_edit: false,
-                edit_history: EditHistory::new("".to_string()),
+                auto_pair: true,
+                explorer: None,
+                pending_close: None,
+                search: None,
+                keymap: default_keymap(),
+                clipboard: default_clipboard(),
             },
-            Command::perform(load_file(default_file()), Message::FileOpened),
+            Command::batch(
+                vec![
+                    Command::perform(load_file(default_file()), Message::FileOpened),
+                    Command::perform(load_keymap(), Message::KeymapLoaded)
+                ]
+            ),
         )
     }
 
@@ -129,11 +759,17 @@ impl Application for Editor {
                     return Command::none();
                 }
 
-                self.is_dirty = self.is_dirty || action.is_edit();
-                self.error = None;
-                self.content.edit(action);
+                let auto_pair = self.auto_pair;
 
-                self.edit_history.add_edit(self.content.text().to_string());
+                if let Some(buffer) = self.active_buffer_mut() {
+                    buffer.error = None;
+                    let before = buffer.content.text();
+                    buffer.apply_edit(auto_pair, action);
+                    let after = buffer.content.text();
+
+                    buffer.edit_history.push_edit(&before, &after);
+                    buffer.is_dirty = !buffer.edit_history.is_clean();
+                }
 
                 Command::none()
             }
@@ -148,42 +784,56 @@ impl Application for Editor {
                 Command::none()
             }
             Message::New => {
-                self.path = None;
-                self.content = text_editor::Content::new();
-                self.is_dirty = true;
+                self.push_buffer(Buffer::new());
 
                 Command::none()
             }
             Message::Open => { Command::perform(pick_file(), Message::FileOpened) }
+            Message::OpenFile(path) => { Command::perform(load_file(path), Message::FileOpened) }
             Message::FileOpened(Ok((path, content))) => {
-                self.path = Some(path);
-                self.content = text_editor::Content::with(&content);
+                let mut buffer = Buffer::new();
+                buffer.path = Some(path);
+                buffer.content = text_editor::Content::with(&content);
+                buffer.is_dirty = false;
 
-                self.edit_history = EditHistory::new(content.as_ref().clone());
-                self.is_dirty = false;
+                self.push_buffer(buffer);
 
                 Command::none()
             }
             Message::FileOpened(Err(error)) => {
-                self.error = Some(error);
+                match self.active_buffer_mut() {
+                    Some(buffer) => buffer.error = Some(error),
+                    None => {
+                        let mut buffer = Buffer::new();
+                        buffer.error = Some(error);
+                        self.push_buffer(buffer);
+                    }
+                }
 
                 Command::none()
             }
             Message::Save => {
-                let text = self.content.text();
+                let Some(buffer) = self.active_buffer_mut() else {
+                    return Command::none();
+                };
 
-                self.edit_history = EditHistory::new(text.clone());
+                let text = buffer.content.text();
+                buffer.edit_history.mark_saved();
 
-                Command::perform(save_file(self.path.clone(), text), Message::FileSaved)
+                Command::perform(save_file(buffer.path.clone(), text), Message::FileSaved)
             }
             Message::FileSaved(Ok(path)) => {
-                self.path = Some(path);
-                self.is_dirty = false;
+                if let Some(buffer) = self.active_buffer_mut() {
+                    buffer.path = Some(path);
+                    buffer.is_dirty = false;
+                }
 
                 Command::none()
             }
             Message::FileSaved(Err(error)) => {
-                self.error = Some(error);
+                if let Some(buffer) = self.active_buffer_mut() {
+                    buffer.error = Some(error);
+                }
 
                 Command::none()
             }
@@ -193,15 +843,280 @@ impl Application for Editor {
                 Command::none()
             }
             Message::Undo => {
-                if self.is_dirty {
-                    if let Some(undo_text) = self.edit_history.undo() {
-                        self.content = text_editor::Content::with(&undo_text);
-                        if self.edit_history.is_clean() {
-                            self.is_dirty = false;
+                if let Some(buffer) = self.active_buffer_mut() {
+                    if let Some(undo_text) = buffer.edit_history.undo(&buffer.content.text()) {
+                        buffer.content = text_editor::Content::with(&undo_text);
+                        buffer.is_dirty = !buffer.edit_history.is_clean();
+                    }
+                }
+
+                Command::none()
+            }
+            Message::Redo => {
+                if let Some(buffer) = self.active_buffer_mut() {
+                    if let Some(redo_text) = buffer.edit_history.redo(&buffer.content.text()) {
+                        buffer.content = text_editor::Content::with(&redo_text);
+                        buffer.is_dirty = !buffer.edit_history.is_clean();
+                    }
+                }
+
+                Command::none()
+            }
+            Message::ToggleAutoPair => {
+                self.auto_pair = !self.auto_pair;
+
+                Command::none()
+            }
+            Message::ToggleExplorer => {
+                if self.explorer.take().is_some() {
+                    return Command::none();
+                }
+
+                let root = self
+                    .active_buffer()
+                    .and_then(|buffer| buffer.path.as_ref())
+                    .and_then(|path| path.parent())
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| PathBuf::from("."));
+
+                self.explorer = Some(TreeState::new(root.clone()));
+
+                Command::perform(read_dir(root), Message::DirLoaded)
+            }
+            Message::OpenDir(path) => {
+                if let Some(explorer) = self.explorer.as_mut() {
+                    if explorer.expanded.remove(&path).is_some() {
+                        return Command::none();
+                    }
+                }
+
+                Command::perform(read_dir(path), Message::DirLoaded)
+            }
+            Message::DirLoaded(Ok((path, entries))) => {
+                if let Some(explorer) = self.explorer.as_mut() {
+                    explorer.expanded.insert(path, entries);
+                }
+
+                Command::none()
+            }
+            Message::DirLoaded(Err(error)) => {
+                if let Some(buffer) = self.active_buffer_mut() {
+                    buffer.error = Some(error);
+                }
+
+                Command::none()
+            }
+            Message::SelectTab(index) => {
+                if index < self.buffers.len() {
+                    self.active = index;
+                }
+
+                Command::none()
+            }
+            Message::CloseTab(index) => {
+                match self.buffers.get(index) {
+                    Some(buffer) if buffer.is_dirty => {
+                        self.pending_close = Some(index);
+                    }
+                    Some(_) => self.close_tab(index),
+                    None => {}
+                }
+
+                Command::none()
+            }
+            Message::ConfirmClose => {
+                if let Some(index) = self.pending_close.take() {
+                    self.close_tab(index);
+                }
+
+                Command::none()
+            }
+            Message::CancelClose => {
+                self.pending_close = None;
+
+                Command::none()
+            }
+            Message::ToggleSearch => {
+                if self.search.take().is_none() {
+                    self.search = Some(SearchState::new());
+                }
+
+                Command::none()
+            }
+            Message::SearchQueryChanged(query) => {
+                if let Some(search) = self.search.as_mut() {
+                    search.query = query;
+                }
+
+                self.refresh_search();
+
+                Command::none()
+            }
+            Message::ReplacementChanged(replacement) => {
+                if let Some(search) = self.search.as_mut() {
+                    search.replacement = replacement;
+                }
+
+                Command::none()
+            }
+            Message::ToggleSearchCase => {
+                if let Some(search) = self.search.as_mut() {
+                    search.case_insensitive = !search.case_insensitive;
+                }
+
+                self.refresh_search();
+
+                Command::none()
+            }
+            Message::ToggleWholeWord => {
+                if let Some(search) = self.search.as_mut() {
+                    search.whole_word = !search.whole_word;
+                }
+
+                self.refresh_search();
+
+                Command::none()
+            }
+            Message::FindNext => {
+                self.refresh_search();
+
+                if let Some(search) = self.search.as_mut() {
+                    if !search.matches.is_empty() {
+                        search.current = (search.current + 1) % search.matches.len();
+                    }
+                }
+
+                self.select_current_match();
+
+                Command::none()
+            }
+            Message::FindPrev => {
+                self.refresh_search();
+
+                if let Some(search) = self.search.as_mut() {
+                    if !search.matches.is_empty() {
+                        search.current = if search.current == 0 {
+                            search.matches.len() - 1
+                        } else {
+                            search.current - 1
+                        };
+                    }
+                }
+
+                self.select_current_match();
+
+                Command::none()
+            }
+            Message::ReplaceOne => {
+                self.refresh_search();
+
+                let Some((range, replacement)) = self.search.as_ref().and_then(|search| {
+                    search.matches.get(search.current).cloned().map(|range| (range, search.replacement.clone()))
+                }) else {
+                    return Command::none();
+                };
+
+                if let Some(buffer) = self.active_buffer_mut() {
+                    let before = buffer.content.text();
+                    let after = replace_byte_range(&before, &range, &replacement);
+
+                    buffer.edit_history.push_edit(&before, &after);
+                    buffer.content = text_editor::Content::with(&after);
+                    buffer.is_dirty = !buffer.edit_history.is_clean();
+                }
+
+                self.refresh_search();
+
+                Command::none()
+            }
+            Message::ReplaceAll => {
+                self.refresh_search();
+
+                let Some((matches, replacement)) = self.search.as_ref().map(|search| {
+                    (search.matches.clone(), search.replacement.clone())
+                }) else {
+                    return Command::none();
+                };
+
+                if !matches.is_empty() {
+                    if let Some(buffer) = self.active_buffer_mut() {
+                        let before = buffer.content.text();
+
+                        let mut after = String::with_capacity(before.len());
+                        let mut cursor = 0;
+                        for range in &matches {
+                            after.push_str(&before[cursor..range.start]);
+                            after.push_str(&replacement);
+                            cursor = range.end;
+                        }
+                        after.push_str(&before[cursor..]);
+
+                        buffer.edit_history.push_edit(&before, &after);
+                        buffer.content = text_editor::Content::with(&after);
+                        buffer.is_dirty = !buffer.edit_history.is_clean();
+                    }
+                }
+
+                self.refresh_search();
+
+                Command::none()
+            }
+            Message::KeymapLoaded(keymap) => {
+                self.keymap = keymap;
+
+                Command::none()
+            }
+            Message::Copy => {
+                if let Some(selection) = self.active_buffer().and_then(|buffer| buffer.content.selection()) {
+                    if let Err(error) = self.clipboard.set_contents(selection) {
+                        if let Some(buffer) = self.active_buffer_mut() {
+                            buffer.error = Some(error);
                         }
                     }
                 }
 
+                Command::none()
+            }
+            Message::Cut => {
+                let selection = self.active_buffer().and_then(|buffer| buffer.content.selection());
+
+                let Some(selection) = selection else {
+                    return Command::none();
+                };
+
+                if let Err(error) = self.clipboard.set_contents(selection) {
+                    if let Some(buffer) = self.active_buffer_mut() {
+                        buffer.error = Some(error);
+                    }
+
+                    return Command::none();
+                }
+
+                if let Some(buffer) = self.active_buffer_mut() {
+                    let before = buffer.content.text();
+                    buffer.content.edit(text_editor::Action::Edit(text_editor::Edit::Delete));
+                    let after = buffer.content.text();
+
+                    buffer.edit_history.push_edit(&before, &after);
+                    buffer.is_dirty = !buffer.edit_history.is_clean();
+                }
+
+                Command::none()
+            }
+            Message::Paste => {
+                let Ok(contents) = self.clipboard.get_contents() else {
+                    return Command::none();
+                };
+
+                if let Some(buffer) = self.active_buffer_mut() {
+                    let before = buffer.content.text();
+                    buffer.content.edit(text_editor::Action::Edit(text_editor::Edit::Paste(Arc::new(contents))));
+                    let after = buffer.content.text();
+
+                    buffer.edit_history.push_edit(&before, &after);
+                    buffer.is_dirty = !buffer.edit_history.is_clean();
+                }
+
                 Command::none()
             }
         }
@@ -209,14 +1124,11 @@ impl Application for Editor {
 
     fn subscription(&self) -> Subscription<Self::Message> {
         // Define as duas handlers
-        let key_press_handler = keyboard::on_key_press(|key_code, modifiers| {
+        let keymap = self.keymap.clone();
+        let key_press_handler = keyboard::on_key_press(move |key_code, modifiers| {
             if modifiers.command() {
-                if key_code == keyboard::KeyCode::Z {
-                    return Some(Message::Undo);
-                }
-
-                if key_code == keyboard::KeyCode::S {
-                    return Some(Message::Save);
+                if let Some(action) = chord_for(key_code, modifiers).and_then(|chord| keymap.lookup(&chord)) {
+                    return Some(action.to_message());
                 }
 
                 return Some(Message::BlockEdit);
@@ -240,48 +1152,102 @@ impl Application for Editor {
     // Lógica que produz os widgets da interface
     // Logic that produces the interface widgets
     fn view(&self) -> Element<'_, Message> {
+        let active_buffer = self.active_buffer();
+
         let controls = row![
             action(new_icon(), "New File", Some(Message::New)),
             action(open_icon(), "Open File", Some(Message::Open)),
-            action(save_icon(), "Save File", self.is_dirty.then_some(Message::Save)),
+            action(
+                save_icon(),
+                "Save File",
+                active_buffer.filter(|buffer| buffer.is_dirty).map(|_| Message::Save)
+            ),
+            toggle_action(
+                auto_pair_icon(),
+                "Auto-close Brackets & Quotes",
+                self.auto_pair,
+                Message::ToggleAutoPair
+            ),
+            toggle_action(
+                explorer_icon(),
+                "Toggle File Explorer",
+                self.explorer.is_some(),
+                Message::ToggleExplorer
+            ),
+            toggle_action(search_icon(), "Find & Replace", self.search.is_some(), Message::ToggleSearch),
+            action(cut_icon(), "Cut", active_buffer.is_some().then_some(Message::Cut)),
+            action(copy_icon(), "Copy", active_buffer.is_some().then_some(Message::Copy)),
+            action(paste_icon(), "Paste", active_buffer.is_some().then_some(Message::Paste)),
             horizontal_space(Length::Fill),
             pick_list(highlighter::Theme::ALL, Some(self.theme), Message::ThemeSelected)
         ].spacing(10);
 
-        let input = text_editor(&self.content)
-            .on_edit(Message::Edit)
-            .highlight::<Highlighter>(
-                highlighter::Settings {
-                    theme: self.theme,
-                    extension: self.path
-                        .as_ref()
-                        .and_then(|path| path.extension()?.to_str())
-                        .unwrap_or("rs")
-                        .to_string(),
-                },
-                |highlight, _theme| highlight.to_format()
-            );
+        let tabs = tab_bar(&self.buffers, self.active);
+
+        let input: Element<'_, Message> = match active_buffer {
+            Some(buffer) => {
+                text_editor(&buffer.content)
+                    .on_edit(Message::Edit)
+                    .highlight::<Highlighter>(
+                        highlighter::Settings {
+                            theme: self.theme,
+                            extension: buffer.path
+                                .as_ref()
+                                .and_then(|path| path.extension()?.to_str())
+                                .unwrap_or("rs")
+                                .to_string(),
+                        },
+                        |highlight, _theme| highlight.to_format()
+                    )
+                    .into()
+            }
+            None => container(text("No open files")).center_x().center_y().into(),
+        };
 
         let status_bar = {
-            let status = if let Some(Error::IOFailed(error)) = self.error.as_ref() {
-                text(error.to_string())
-            } else {
-                match self.path.as_deref().and_then(Path::to_str) {
-                    Some(path) => text(path).size(14),
-                    None => text("New file"),
+            let status = match active_buffer.and_then(|buffer| buffer.error.as_ref()) {
+                Some(Error::IOFailed(error)) => text(error.to_string()),
+                _ => {
+                    match active_buffer.and_then(|buffer| buffer.path.as_deref()) {
+                        Some(path) => text(path.to_string_lossy().into_owned()).size(14),
+                        None => text("New file"),
+                    }
                 }
             };
 
-            let position = {
-                let (line, column) = self.content.cursor_position();
-
-                text(format!("{}:{}", line + 1, column + 1))
+            let position = match active_buffer {
+                Some(buffer) => {
+                    let (line, column) = buffer.content.cursor_position();
+                    text(format!("{}:{}", line + 1, column + 1))
+                }
+                None => text(""),
             };
 
             row![status, horizontal_space(Length::Fill), position]
         };
 
-        container(column![controls, input, status_bar].spacing(10)).padding(10).into()
+        let mut editor = column![tabs, controls].spacing(10);
+
+        if let Some(index) = self.pending_close {
+            if let Some(buffer) = self.buffers.get(index) {
+                editor = editor.push(close_confirmation(buffer));
+            }
+        }
+
+        editor = editor.push(input);
+
+        if let Some(search) = &self.search {
+            editor = editor.push(search_panel(search));
+        }
+
+        editor = editor.push(status_bar);
+
+        let content: Element<'_, Message> = match &self.explorer {
+            Some(explorer) => row![explorer_view(explorer), editor].spacing(10).into(),
+            None => editor.into(),
+        };
+
+        container(content).padding(10).into()
     }
 
     // Theme provider method
@@ -309,6 +1275,89 @@ fn action<'a>(
         .into()
 }
 
+// Como `action`, mas para controles com estado ligado/desligado em vez de
+// apenas habilitado/desabilitado.
+fn toggle_action<'a>(
+    content: Element<'a, Message>,
+    label: &str,
+    is_active: bool,
+    on_press: Message
+) -> Element<'a, Message> {
+    tooltip(
+        button(container(content).width(30).center_x())
+            .on_press(on_press)
+            .padding([5, 10])
+            .style(if is_active { theme::Button::Primary } else { theme::Button::Secondary }),
+        label,
+        tooltip::Position::FollowCursor
+    )
+        .style(theme::Container::Box)
+        .into()
+}
+
+// Tira de abas: um botão por buffer (nome + indicador de sujidade) seguido
+// de um botão de fechar.
+fn tab_bar<'a>(buffers: &[Buffer], active: usize) -> Element<'a, Message> {
+    let mut tabs = row![].spacing(4);
+
+    for (index, buffer) in buffers.iter().enumerate() {
+        let label = if buffer.is_dirty {
+            format!("{} ●", buffer.title())
+        } else {
+            buffer.title()
+        };
+
+        let select = button(text(label))
+            .on_press(Message::SelectTab(index))
+            .padding([5, 10])
+            .style(if index == active { theme::Button::Primary } else { theme::Button::Secondary });
+
+        let close = button(text("×"))
+            .on_press(Message::CloseTab(index))
+            .padding([5, 8])
+            .style(theme::Button::Secondary);
+
+        tabs = tabs.push(row![select, close].spacing(2));
+    }
+
+    tabs.into()
+}
+
+fn close_confirmation<'a>(buffer: &Buffer) -> Element<'a, Message> {
+    row![
+        text(format!("Discard changes to {}?", buffer.title())),
+        horizontal_space(Length::Fill),
+        button(text("Discard")).on_press(Message::ConfirmClose).style(theme::Button::Destructive),
+        button(text("Cancel")).on_press(Message::CancelClose).style(theme::Button::Secondary)
+    ]
+        .spacing(10)
+        .into()
+}
+
+fn search_panel<'a>(search: &SearchState) -> Element<'a, Message> {
+    let counter = if search.matches.is_empty() {
+        String::from("0 / 0")
+    } else {
+        format!("{} / {}", search.current + 1, search.matches.len())
+    };
+
+    row![
+        text_input("Find", &search.query).on_input(Message::SearchQueryChanged).width(Length::FillPortion(2)),
+        text_input("Replace", &search.replacement)
+            .on_input(Message::ReplacementChanged)
+            .width(Length::FillPortion(2)),
+        checkbox("Aa", search.case_insensitive).on_toggle(|_| Message::ToggleSearchCase),
+        checkbox("word", search.whole_word).on_toggle(|_| Message::ToggleWholeWord),
+        text(counter),
+        button(text("Prev")).on_press(Message::FindPrev),
+        button(text("Next")).on_press(Message::FindNext),
+        button(text("Replace")).on_press(Message::ReplaceOne),
+        button(text("Replace All")).on_press(Message::ReplaceAll)
+    ]
+        .spacing(8)
+        .into()
+}
+
 fn new_icon<'a>() -> Element<'a, Message> {
     icon('\u{E800}')
 }
@@ -321,6 +1370,76 @@ fn save_icon<'a>() -> Element<'a, Message> {
     icon('\u{E801}')
 }
 
+fn auto_pair_icon<'a>() -> Element<'a, Message> {
+    icon('\u{E802}')
+}
+
+fn explorer_icon<'a>() -> Element<'a, Message> {
+    icon('\u{E803}')
+}
+
+fn search_icon<'a>() -> Element<'a, Message> {
+    icon('\u{E804}')
+}
+
+fn cut_icon<'a>() -> Element<'a, Message> {
+    icon('\u{E805}')
+}
+
+fn copy_icon<'a>() -> Element<'a, Message> {
+    icon('\u{E806}')
+}
+
+fn paste_icon<'a>() -> Element<'a, Message> {
+    icon('\u{E807}')
+}
+
+fn explorer_view<'a>(explorer: &TreeState) -> Element<'a, Message> {
+    container(
+        column![text("Explorer").size(14), render_dir(&explorer.root, explorer, 0)].spacing(5)
+    )
+        .width(220)
+        .padding(10)
+        .style(theme::Container::Box)
+        .into()
+}
+
+// Renderiza os filhos já carregados de `dir`; diretórios expandidos
+// aparecem logo a seguir, recursivamente, indentados por `depth`.
+fn render_dir<'a>(dir: &Path, explorer: &TreeState, depth: usize) -> Element<'a, Message> {
+    let indent = "  ".repeat(depth);
+    let mut entries = column![].spacing(2);
+
+    if let Some(children) = explorer.expanded.get(dir) {
+        for entry in children {
+            if entry.is_dir {
+                let is_open = explorer.expanded.contains_key(&entry.path);
+                let arrow = if is_open { "▾" } else { "▸" };
+
+                entries = entries.push(
+                    button(text(format!("{indent}{arrow} {}", entry.name)))
+                        .on_press(Message::OpenDir(entry.path.clone()))
+                        .style(theme::Button::Text)
+                        .width(Length::Fill)
+                );
+
+                if is_open {
+                    entries = entries.push(render_dir(&entry.path, explorer, depth + 1));
+                }
+            } else {
+                entries = entries.push(
+                    button(text(format!("{indent}  {}", entry.name)))
+                        .on_press(Message::OpenFile(entry.path.clone()))
+                        .style(theme::Button::Text)
+                        .width(Length::Fill)
+                );
+            }
+        }
+    }
+
+    entries.into()
+}
+
 fn icon<'a>(codepoint: char) -> Element<'a, Message> {
     const ICON_FONT: Font = Font::with_name("editor-icons");
 
@@ -351,6 +1470,55 @@ async fn load_file(path: PathBuf) -> Result<(PathBuf, Arc<String>), Error> {
     Ok((path, contents))
 }
 
+async fn read_dir(path: PathBuf) -> Result<(PathBuf, Vec<DirEntry>), Error> {
+    let mut dir = tokio::fs::read_dir(&path).await.map_err(|error| Error::IOFailed(error.kind()))?;
+
+    let mut entries = Vec::new();
+    while
+        let Some(entry) = dir
+            .next_entry().await
+            .map_err(|error| Error::IOFailed(error.kind()))?
+    {
+        let file_type = entry
+            .file_type().await
+            .map_err(|error| Error::IOFailed(error.kind()))?;
+
+        entries.push(DirEntry {
+            path: entry.path(),
+            name: entry.file_name().to_string_lossy().into_owned(),
+            is_dir: file_type.is_dir(),
+        });
+    }
+
+    entries.sort_by(|a, b| {
+        match (a.is_dir, b.is_dir) {
+            (true, false) => std::cmp::Ordering::Less,
+            (false, true) => std::cmp::Ordering::Greater,
+            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        }
+    });
+
+    Ok((path, entries))
+}
+
+fn keymap_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("cool-editor").join("keys.toml"))
+}
+
+// Carrega o keys.toml do utilizador por cima dos atalhos padrão; qualquer
+// problema (ficheiro ausente, TOML inválido) simplesmente mantém os padrões.
+async fn load_keymap() -> Keymap {
+    let mut keymap = default_keymap();
+
+    if let Some(path) = keymap_path() {
+        if let Ok(source) = tokio::fs::read_to_string(&path).await {
+            keymap.bindings.extend(parse_keymap(&source));
+        }
+    }
+
+    keymap
+}
+
 async fn save_file(path: Option<PathBuf>, text: String) -> Result<PathBuf, Error> {
     let path = if let Some(path) = path {
         path
@@ -372,4 +1540,5 @@ async fn save_file(path: Option<PathBuf>, text: String) -> Result<PathBuf, Error
 enum Error {
     DialogClosed,
     IOFailed(io::ErrorKind),
+    ClipboardUnavailable,
 }